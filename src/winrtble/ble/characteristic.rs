@@ -12,15 +12,31 @@
 // Copyright (c) 2014 The Rust Project Developers
 
 use super::super::bindings;
-use crate::{api::WriteType, Error, Result};
+use crate::{api::CharPropFlags, api::WriteType, Error, Result};
 
 use bindings::windows::devices::bluetooth::generic_attribute_profile::{
-    GattCharacteristic, GattClientCharacteristicConfigurationDescriptorValue,
-    GattCommunicationStatus, GattValueChangedEventArgs, GattWriteOption,
+    GattCharacteristic, GattCharacteristicProperties,
+    GattClientCharacteristicConfigurationDescriptorValue, GattCommunicationStatus, GattDescriptor,
+    GattValueChangedEventArgs, GattWriteOption,
 };
-use bindings::windows::foundation::{EventRegistrationToken, TypedEventHandler};
+use bindings::windows::foundation::{EventRegistrationToken, Guid, TypedEventHandler};
 use bindings::windows::storage::streams::{DataReader, DataWriter};
 use log::{debug, trace};
+use uuid::Uuid;
+
+fn guid_to_uuid(guid: Guid) -> Uuid {
+    Uuid::from_fields(guid.Data1, guid.Data2, guid.Data3, &guid.Data4).unwrap()
+}
+
+pub(crate) fn uuid_to_guid(uuid: Uuid) -> Guid {
+    let fields = uuid.as_fields();
+    Guid {
+        Data1: fields.0,
+        Data2: fields.1,
+        Data3: fields.2,
+        Data4: *fields.3,
+    }
+}
 
 pub type NotifiyEventHandler = Box<dyn Fn(Vec<u8>) + Send>;
 
@@ -33,6 +49,78 @@ impl Into<GattWriteOption> for WriteType {
     }
 }
 
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SubscribeType {
+    Notify,
+    Indicate,
+    Auto,
+}
+
+fn has_property(properties: GattCharacteristicProperties, flag: GattCharacteristicProperties) -> bool {
+    properties & flag == flag
+}
+
+fn convert_properties(properties: GattCharacteristicProperties) -> CharPropFlags {
+    let mut flags = CharPropFlags::default();
+    if has_property(properties, GattCharacteristicProperties::Broadcast) {
+        flags |= CharPropFlags::BROADCAST;
+    }
+    if has_property(properties, GattCharacteristicProperties::Read) {
+        flags |= CharPropFlags::READ;
+    }
+    if has_property(properties, GattCharacteristicProperties::WriteWithoutResponse) {
+        flags |= CharPropFlags::WRITE_WITHOUT_RESPONSE;
+    }
+    if has_property(properties, GattCharacteristicProperties::Write) {
+        flags |= CharPropFlags::WRITE;
+    }
+    if has_property(properties, GattCharacteristicProperties::Notify) {
+        flags |= CharPropFlags::NOTIFY;
+    }
+    if has_property(properties, GattCharacteristicProperties::Indicate) {
+        flags |= CharPropFlags::INDICATE;
+    }
+    if has_property(
+        properties,
+        GattCharacteristicProperties::AuthenticatedSignedWrites,
+    ) {
+        flags |= CharPropFlags::AUTHENTICATED_SIGNED_WRITES;
+    }
+    if has_property(properties, GattCharacteristicProperties::ExtendedProperties) {
+        flags |= CharPropFlags::EXTENDED_PROPERTIES;
+    }
+    flags
+}
+
+pub(crate) fn convert_to_winrt_properties(flags: CharPropFlags) -> GattCharacteristicProperties {
+    let mut properties = GattCharacteristicProperties::None;
+    if flags.contains(CharPropFlags::BROADCAST) {
+        properties |= GattCharacteristicProperties::Broadcast;
+    }
+    if flags.contains(CharPropFlags::READ) {
+        properties |= GattCharacteristicProperties::Read;
+    }
+    if flags.contains(CharPropFlags::WRITE_WITHOUT_RESPONSE) {
+        properties |= GattCharacteristicProperties::WriteWithoutResponse;
+    }
+    if flags.contains(CharPropFlags::WRITE) {
+        properties |= GattCharacteristicProperties::Write;
+    }
+    if flags.contains(CharPropFlags::NOTIFY) {
+        properties |= GattCharacteristicProperties::Notify;
+    }
+    if flags.contains(CharPropFlags::INDICATE) {
+        properties |= GattCharacteristicProperties::Indicate;
+    }
+    if flags.contains(CharPropFlags::AUTHENTICATED_SIGNED_WRITES) {
+        properties |= GattCharacteristicProperties::AuthenticatedSignedWrites;
+    }
+    if flags.contains(CharPropFlags::EXTENDED_PROPERTIES) {
+        properties |= GattCharacteristicProperties::ExtendedProperties;
+    }
+    properties
+}
+
 pub struct BLECharacteristic {
     characteristic: GattCharacteristic,
     notify_token: Option<EventRegistrationToken>,
@@ -49,6 +137,10 @@ impl BLECharacteristic {
         }
     }
 
+    pub fn properties(&self) -> Result<CharPropFlags> {
+        Ok(convert_properties(self.characteristic.characteristic_properties()?))
+    }
+
     pub fn write_value(&self, data: &[u8], write_type: WriteType) -> Result<()> {
         let writer = DataWriter::new().unwrap();
         writer.write_bytes(data)?;
@@ -81,7 +173,84 @@ impl BLECharacteristic {
         }
     }
 
+    pub fn get_descriptors(&self) -> Result<Vec<Uuid>> {
+        let result = self.characteristic.get_descriptors_async()?.get()?;
+        if result.status()? != GattCommunicationStatus::Success {
+            return Err(Error::Other(format!(
+                "Windows UWP threw error on get_descriptors: {:?}",
+                result.status()?
+            )));
+        }
+        let descriptors = result.descriptors()?;
+        let mut uuids = Vec::with_capacity(descriptors.size()? as usize);
+        for descriptor in descriptors {
+            uuids.push(guid_to_uuid(descriptor.uuid()?));
+        }
+        Ok(uuids)
+    }
+
+    fn find_descriptor(&self, uuid: Uuid) -> Result<GattDescriptor> {
+        let result = self
+            .characteristic
+            .get_descriptors_for_uuid_async(uuid_to_guid(uuid))?
+            .get()?;
+        if result.status()? != GattCommunicationStatus::Success {
+            return Err(Error::Other(format!(
+                "Windows UWP threw error on get_descriptors_for_uuid: {:?}",
+                result.status()?
+            )));
+        }
+        let descriptors = result.descriptors()?;
+        if descriptors.size()? == 0 {
+            return Err(Error::Other(format!("No descriptor found for uuid {}", uuid)));
+        }
+        Ok(descriptors.get_at(0)?)
+    }
+
+    pub fn read_descriptor(&self, uuid: Uuid) -> Result<Vec<u8>> {
+        let descriptor = self.find_descriptor(uuid)?;
+        let result = descriptor.read_value_async()?.get()?;
+        if result.status()? == GattCommunicationStatus::Success {
+            let value = result.value()?;
+            let reader = DataReader::from_buffer(&value)?;
+            let len = reader.unconsumed_buffer_length()? as usize;
+            let mut input = vec![0u8; len];
+            reader.read_bytes(&mut input[0..len])?;
+            Ok(input)
+        } else {
+            Err(Error::Other(format!(
+                "Windows UWP threw error on read_descriptor: {:?}",
+                result.status()?
+            )))
+        }
+    }
+
+    pub fn write_descriptor(&self, uuid: Uuid, data: &[u8]) -> Result<()> {
+        let descriptor = self.find_descriptor(uuid)?;
+        let writer = DataWriter::new().unwrap();
+        writer.write_bytes(data)?;
+        let buffer = writer.detach_buffer()?;
+        let status = descriptor.write_value_async(&buffer)?.get()?;
+        if status == GattCommunicationStatus::Success {
+            Ok(())
+        } else {
+            Err(Error::Other(format!(
+                "Windows UWP threw error on write_descriptor: {:?}",
+                status
+            )))
+        }
+    }
+
     pub fn subscribe(&mut self, on_value_changed: NotifiyEventHandler) -> Result<()> {
+        self.subscribe_with_type(on_value_changed, SubscribeType::Auto)
+    }
+
+    pub fn subscribe_with_type(
+        &mut self,
+        on_value_changed: NotifiyEventHandler,
+        subscribe_type: SubscribeType,
+    ) -> Result<()> {
+        let config = self.cccd_value_for(subscribe_type)?;
         let value_handler = TypedEventHandler::new(
             move |_: &Option<GattCharacteristic>, args: &Option<GattValueChangedEventArgs>| {
                 if let Some(args) = args {
@@ -98,7 +267,6 @@ impl BLECharacteristic {
         );
         let token = self.characteristic.value_changed(&value_handler)?;
         self.notify_token = Some(token);
-        let config = GattClientCharacteristicConfigurationDescriptorValue::Notify;
         let status = self
             .characteristic
             .write_client_characteristic_configuration_descriptor_async(config)?
@@ -111,6 +279,38 @@ impl BLECharacteristic {
         }
     }
 
+    fn cccd_value_for(
+        &self,
+        subscribe_type: SubscribeType,
+    ) -> Result<GattClientCharacteristicConfigurationDescriptorValue> {
+        let properties = self.characteristic.characteristic_properties()?;
+        let supports_notify = has_property(properties, GattCharacteristicProperties::Notify);
+        let supports_indicate = has_property(properties, GattCharacteristicProperties::Indicate);
+        match subscribe_type {
+            SubscribeType::Notify if supports_notify => {
+                Ok(GattClientCharacteristicConfigurationDescriptorValue::Notify)
+            }
+            SubscribeType::Notify => Err(Error::Other(
+                "Characteristic does not support notifications".into(),
+            )),
+            SubscribeType::Indicate if supports_indicate => {
+                Ok(GattClientCharacteristicConfigurationDescriptorValue::Indicate)
+            }
+            SubscribeType::Indicate => Err(Error::Other(
+                "Characteristic does not support indications".into(),
+            )),
+            SubscribeType::Auto if supports_notify => {
+                Ok(GattClientCharacteristicConfigurationDescriptorValue::Notify)
+            }
+            SubscribeType::Auto if supports_indicate => {
+                Ok(GattClientCharacteristicConfigurationDescriptorValue::Indicate)
+            }
+            SubscribeType::Auto => Err(Error::Other(
+                "Characteristic supports neither notifications nor indications".into(),
+            )),
+        }
+    }
+
     pub fn unsubscribe(&mut self) -> Result<()> {
         if let Some(token) = &self.notify_token {
             self.characteristic.remove_value_changed(token)?;
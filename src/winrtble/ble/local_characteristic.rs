@@ -0,0 +1,163 @@
+// btleplug Source Code File
+//
+// Copyright 2020 Nonpolynomial Labs LLC. All rights reserved.
+//
+// Licensed under the BSD 3-Clause license. See LICENSE file in the project root
+// for full license information.
+//
+// Some portions of this file are taken and/or modified from Rumble
+// (https://github.com/mwylde/rumble), using a dual MIT/Apache License under the
+// following copyright:
+//
+// Copyright (c) 2014 The Rust Project Developers
+
+use super::super::bindings;
+use crate::{Error, Result};
+
+use bindings::windows::devices::bluetooth::generic_attribute_profile::{
+    GattCommunicationStatus, GattLocalCharacteristic, GattReadRequestedEventArgs, GattWriteOption,
+    GattWriteRequestedEventArgs,
+};
+use bindings::windows::foundation::{EventRegistrationToken, TypedEventHandler};
+use bindings::windows::storage::streams::{DataReader, DataWriter};
+use log::{debug, trace};
+use uuid::Uuid;
+
+pub type ReadRequestHandler = Box<dyn Fn() -> Vec<u8> + Send + Sync>;
+pub type WriteRequestHandler = Box<dyn Fn(Vec<u8>) + Send + Sync>;
+pub type SubscriptionChangeHandler = Box<dyn Fn(bool) + Send + Sync>;
+
+pub struct BLELocalCharacteristic {
+    uuid: Uuid,
+    characteristic: GattLocalCharacteristic,
+    read_token: Option<EventRegistrationToken>,
+    write_token: Option<EventRegistrationToken>,
+    subscription_token: Option<EventRegistrationToken>,
+}
+
+unsafe impl Send for BLELocalCharacteristic {}
+unsafe impl Sync for BLELocalCharacteristic {}
+
+impl BLELocalCharacteristic {
+    pub(crate) fn new(uuid: Uuid, characteristic: GattLocalCharacteristic) -> Self {
+        BLELocalCharacteristic {
+            uuid,
+            characteristic,
+            read_token: None,
+            write_token: None,
+            subscription_token: None,
+        }
+    }
+
+    pub fn uuid(&self) -> Uuid {
+        self.uuid
+    }
+
+    pub fn on_read(&mut self, handler: ReadRequestHandler) -> Result<()> {
+        if let Some(token) = self.read_token.take() {
+            self.characteristic.remove_read_requested(&token)?;
+        }
+        let token = self.characteristic.read_requested(&TypedEventHandler::new(
+            move |_: &Option<GattLocalCharacteristic>,
+                  args: &Option<GattReadRequestedEventArgs>| {
+                if let Some(args) = args {
+                    let deferral = args.get_deferral()?;
+                    let request = args.get_request_async()?.get()?;
+                    let value = handler();
+                    let writer = DataWriter::new().unwrap();
+                    writer.write_bytes(&value)?;
+                    let buffer = writer.detach_buffer()?;
+                    request.respond_with_value(&buffer)?;
+                    deferral.complete()?;
+                }
+                Ok(())
+            },
+        ))?;
+        self.read_token = Some(token);
+        Ok(())
+    }
+
+    pub fn on_write(&mut self, handler: WriteRequestHandler) -> Result<()> {
+        if let Some(token) = self.write_token.take() {
+            self.characteristic.remove_write_requested(&token)?;
+        }
+        let token = self.characteristic.write_requested(&TypedEventHandler::new(
+            move |_: &Option<GattLocalCharacteristic>,
+                  args: &Option<GattWriteRequestedEventArgs>| {
+                if let Some(args) = args {
+                    let deferral = args.get_deferral()?;
+                    let request = args.get_request_async()?.get()?;
+                    let value = request.value()?;
+                    let reader = DataReader::from_buffer(&value)?;
+                    let len = reader.unconsumed_buffer_length()? as usize;
+                    let mut input = vec![0u8; len];
+                    reader.read_bytes(&mut input[0..len])?;
+                    trace!("write requested {:?}", input);
+                    handler(input);
+                    if request.option()? == GattWriteOption::WriteWithResponse {
+                        request.respond()?;
+                    }
+                    deferral.complete()?;
+                }
+                Ok(())
+            },
+        ))?;
+        self.write_token = Some(token);
+        Ok(())
+    }
+
+    pub fn on_subscription_change(&mut self, handler: SubscriptionChangeHandler) -> Result<()> {
+        if let Some(token) = self.subscription_token.take() {
+            self.characteristic.remove_subscribed_clients_changed(&token)?;
+        }
+        let characteristic = self.characteristic.clone();
+        let token = self
+            .characteristic
+            .subscribed_clients_changed(&TypedEventHandler::new(
+                move |_: &Option<GattLocalCharacteristic>, _: &Option<bindings::windows::foundation::IInspectable>| {
+                    let has_subscribers = characteristic.subscribed_clients()?.size()? > 0;
+                    trace!("subscribed clients changed, has_subscribers={}", has_subscribers);
+                    handler(has_subscribers);
+                    Ok(())
+                },
+            ))?;
+        self.subscription_token = Some(token);
+        Ok(())
+    }
+
+    pub fn notify(&self, data: &[u8]) -> Result<()> {
+        let writer = DataWriter::new().unwrap();
+        writer.write_bytes(data)?;
+        let buffer = writer.detach_buffer()?;
+        let status = self.characteristic.notify_value_async(&buffer)?.get()?;
+        trace!("notify {:?}", status);
+        if status == GattCommunicationStatus::Success {
+            Ok(())
+        } else {
+            Err(Error::Other(format!(
+                "Windows UWP threw error on notify: {:?}",
+                status
+            )))
+        }
+    }
+}
+
+impl Drop for BLELocalCharacteristic {
+    fn drop(&mut self) {
+        if let Some(token) = &self.read_token {
+            if let Err(err) = self.characteristic.remove_read_requested(token) {
+                debug!("Drop:remove_read_requested {:?}", err);
+            }
+        }
+        if let Some(token) = &self.write_token {
+            if let Err(err) = self.characteristic.remove_write_requested(token) {
+                debug!("Drop:remove_write_requested {:?}", err);
+            }
+        }
+        if let Some(token) = &self.subscription_token {
+            if let Err(err) = self.characteristic.remove_subscribed_clients_changed(token) {
+                debug!("Drop:remove_subscribed_clients_changed {:?}", err);
+            }
+        }
+    }
+}
@@ -0,0 +1,22 @@
+// btleplug Source Code File
+//
+// Copyright 2020 Nonpolynomial Labs LLC. All rights reserved.
+//
+// Licensed under the BSD 3-Clause license. See LICENSE file in the project root
+// for full license information.
+//
+// Some portions of this file are taken and/or modified from Rumble
+// (https://github.com/mwylde/rumble), using a dual MIT/Apache License under the
+// following copyright:
+//
+// Copyright (c) 2014 The Rust Project Developers
+
+pub mod characteristic;
+pub mod local_characteristic;
+pub mod peripheral;
+
+pub use characteristic::{BLECharacteristic, NotifiyEventHandler, SubscribeType};
+pub use local_characteristic::{
+    BLELocalCharacteristic, ReadRequestHandler, SubscriptionChangeHandler, WriteRequestHandler,
+};
+pub use peripheral::BLEPeripheral;
@@ -0,0 +1,100 @@
+// btleplug Source Code File
+//
+// Copyright 2020 Nonpolynomial Labs LLC. All rights reserved.
+//
+// Licensed under the BSD 3-Clause license. See LICENSE file in the project root
+// for full license information.
+//
+// Some portions of this file are taken and/or modified from Rumble
+// (https://github.com/mwylde/rumble), using a dual MIT/Apache License under the
+// following copyright:
+//
+// Copyright (c) 2014 The Rust Project Developers
+
+use super::super::bindings;
+use super::characteristic::{convert_to_winrt_properties, uuid_to_guid};
+use super::local_characteristic::BLELocalCharacteristic;
+use crate::{api::CharPropFlags, Error, Result};
+
+use bindings::windows::devices::bluetooth::generic_attribute_profile::{
+    GattLocalCharacteristicParameters, GattServiceProvider,
+    GattServiceProviderAdvertisingParameters,
+};
+use bindings::windows::devices::bluetooth::BluetoothError;
+use log::trace;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+pub struct BLEPeripheral {
+    service_provider: GattServiceProvider,
+    characteristics: HashMap<Uuid, BLELocalCharacteristic>,
+}
+
+unsafe impl Send for BLEPeripheral {}
+unsafe impl Sync for BLEPeripheral {}
+
+impl BLEPeripheral {
+    pub fn new(service_uuid: Uuid) -> Result<Self> {
+        let result = GattServiceProvider::create_async(uuid_to_guid(service_uuid))?.get()?;
+        if result.error()? != BluetoothError::Success {
+            return Err(Error::Other(format!(
+                "Windows UWP threw error on create_service_provider: {:?}",
+                result.error()?
+            )));
+        }
+        let service_provider = result.service_provider()?;
+        Ok(BLEPeripheral {
+            service_provider,
+            characteristics: HashMap::new(),
+        })
+    }
+
+    pub fn add_characteristic(
+        &mut self,
+        uuid: Uuid,
+        properties: CharPropFlags,
+    ) -> Result<&mut BLELocalCharacteristic> {
+        let params = GattLocalCharacteristicParameters::new()?;
+        params.set_characteristic_properties(convert_to_winrt_properties(properties))?;
+        let result = self
+            .service_provider
+            .service()?
+            .create_characteristic_async(uuid_to_guid(uuid), &params)?
+            .get()?;
+        if result.error()? != BluetoothError::Success {
+            return Err(Error::Other(format!(
+                "Windows UWP threw error on create_characteristic: {:?}",
+                result.error()?
+            )));
+        }
+        let characteristic = BLELocalCharacteristic::new(uuid, result.characteristic()?);
+        self.characteristics.insert(uuid, characteristic);
+        Ok(self.characteristics.get_mut(&uuid).unwrap())
+    }
+
+    pub fn characteristic(&self, uuid: Uuid) -> Option<&BLELocalCharacteristic> {
+        self.characteristics.get(&uuid)
+    }
+
+    pub fn characteristic_mut(&mut self, uuid: Uuid) -> Option<&mut BLELocalCharacteristic> {
+        self.characteristics.get_mut(&uuid)
+    }
+
+    /// WinRT doesn't let `GattServiceProvider` override the advertised local name; that comes
+    /// from the system Bluetooth radio's configuration.
+    pub fn start_advertising(&self) -> Result<()> {
+        let params = GattServiceProviderAdvertisingParameters::new()?;
+        params.set_is_discoverable(true)?;
+        params.set_is_connectable(true)?;
+        self.service_provider
+            .start_advertising_with_parameters(&params)?;
+        trace!("started advertising {:?}", self.service_provider);
+        Ok(())
+    }
+
+    pub fn stop_advertising(&self) -> Result<()> {
+        self.service_provider.stop_advertising()?;
+        trace!("stopped advertising {:?}", self.service_provider);
+        Ok(())
+    }
+}